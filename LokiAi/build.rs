@@ -0,0 +1,50 @@
+use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn git(args: &[&str]) -> Option<String> {
+    Command::new("git")
+        .args(args)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|out| out.trim().to_string())
+        .filter(|out| !out.is_empty())
+}
+
+fn main() {
+    let git_sha = git(&["rev-parse", "--short", "HEAD"]).unwrap_or_else(|| "unknown".to_string());
+    let build_timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    println!("cargo:rustc-env=GIT_SHA={}", git_sha);
+    println!("cargo:rustc-env=BUILD_TIMESTAMP={}", build_timestamp);
+
+    // Rerun only when the checked-out commit changes. HEAD lives in the
+    // worktree's own git dir and changes on branch switches; branch refs live
+    // in the common dir, shared by all worktrees. A commit rewrites a loose
+    // ref under refs/heads, or packed-refs after `git pack-refs`/`git gc`, so
+    // watch the refs/heads directory rather than a ref file that may not
+    // exist. Missing paths are skipped, since cargo would treat them as always
+    // dirty and rebuild on every run.
+    println!("cargo:rerun-if-changed=build.rs");
+    if let Some(git_dir) = git(&["rev-parse", "--absolute-git-dir"]) {
+        let git_dir = PathBuf::from(git_dir);
+        // --git-common-dir may print a path relative to the working directory.
+        let common_dir = git(&["rev-parse", "--git-common-dir"])
+            .and_then(|dir| env::current_dir().ok().map(|cwd| cwd.join(dir)))
+            .unwrap_or_else(|| git_dir.clone());
+        let watched = [
+            git_dir.join("HEAD"),
+            common_dir.join("packed-refs"),
+            common_dir.join("refs").join("heads"),
+        ];
+        for path in watched.iter().filter(|path| path.exists()) {
+            println!("cargo:rerun-if-changed={}", path.display());
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use std::time::Instant;
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::middleware::Next;
+use actix_web::Error;
+
+use crate::routes;
+
+/// Paths polled by load balancers and uptime checks, with or without the
+/// version prefix. Logging them would drown every other request.
+const PROBE_PATHS: &[&str] = &["/health", "/healthz"];
+
+/// Logs method, path, status, client IP and latency for every request except
+/// health probes.
+pub async fn access_log(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    if is_probe(req.path()) {
+        return next.call(req).await;
+    }
+
+    let method = req.method().clone();
+    let path = redact_path(req.path());
+    let client_ip = req
+        .peer_addr()
+        .map(|addr| addr.ip().to_string())
+        .unwrap_or_else(|| "-".to_string());
+    let started = Instant::now();
+
+    let res = next.call(req).await;
+
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+    let status = match &res {
+        Ok(res) => res.status(),
+        Err(e) => e.as_response_error().status_code(),
+    };
+    tracing::info!(
+        %method,
+        %path,
+        status = status.as_u16(),
+        %client_ip,
+        elapsed_ms,
+        "request"
+    );
+
+    res
+}
+
+fn is_probe(path: &str) -> bool {
+    let path = path.strip_prefix(routes::V1).unwrap_or(path);
+    PROBE_PATHS.contains(&path)
+}
+
+/// Shortens any wallet address in the path to `0x1234...abcd` so access logs
+/// don't tie full addresses to client IPs.
+fn redact_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| {
+            if is_wallet_address(segment) {
+                format!("{}...{}", &segment[..6], &segment[segment.len() - 4..])
+            } else {
+                segment.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn is_wallet_address(segment: &str) -> bool {
+    segment.len() == 42
+        && (segment.starts_with("0x") || segment.starts_with("0X"))
+        && segment[2..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+    use std::sync::{Arc, Mutex};
+
+    use actix_web::test::{call_service, init_service, TestRequest};
+    use actix_web::{middleware, web, App};
+
+    const WALLET: &str = "0x52908400098527886E0F7030069857D2E4169EE7";
+
+    #[test]
+    fn wallet_addresses_are_recognised() {
+        assert!(is_wallet_address(WALLET));
+        assert!(is_wallet_address(&WALLET.to_lowercase()));
+        assert!(!is_wallet_address("0x1234"));
+        assert!(!is_wallet_address(&WALLET.replace('E', "G")));
+        assert!(!is_wallet_address(&format!("1x{}", &WALLET[2..])));
+    }
+
+    #[test]
+    fn wallet_addresses_in_paths_are_shortened() {
+        assert_eq!(
+            redact_path(&format!("/api/settings/{}/export", WALLET)),
+            "/api/settings/0x5290...9EE7/export"
+        );
+        assert_eq!(redact_path("/users/42"), "/users/42");
+        assert_eq!(redact_path("/"), "/");
+    }
+
+    #[test]
+    fn probes_are_recognised_with_and_without_version() {
+        assert!(is_probe("/health"));
+        assert!(is_probe("/v1/healthz"));
+        assert!(!is_probe("/time"));
+        assert!(!is_probe("/v1/health/extra"));
+    }
+
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[actix_web::test]
+    async fn requests_are_logged_with_redacted_path() {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let app = init_service(
+            App::new()
+                .wrap(middleware::from_fn(access_log))
+                .route("/wallets/{address}", web::get().to(|| async { "ok" }))
+                .route("/health", web::get().to(|| async { "ok" })),
+        )
+        .await;
+        let uri = format!("/wallets/{}", WALLET);
+        call_service(&app, TestRequest::get().uri(&uri).to_request()).await;
+        call_service(&app, TestRequest::get().uri("/health").to_request()).await;
+
+        let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines.len(),
+            1,
+            "only the non-probe request is logged: {}",
+            output
+        );
+        let line = lines[0];
+        assert!(line.contains("method=GET"), "{}", line);
+        assert!(line.contains("path=/wallets/0x5290...9EE7"), "{}", line);
+        assert!(line.contains("status=200"), "{}", line);
+        assert!(line.contains("client_ip="), "{}", line);
+        assert!(line.contains("elapsed_ms="), "{}", line);
+        assert!(!line.contains(WALLET), "{}", line);
+    }
+}
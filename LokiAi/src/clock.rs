@@ -0,0 +1,40 @@
+use actix_web::{HttpResponse, Responder};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct TimeResponse {
+    unix: i64,
+    rfc3339: String,
+}
+
+/// The one timestamp format used in responses: RFC 3339 in UTC with
+/// millisecond precision, e.g. `2024-05-01T12:00:00.000Z`.
+pub fn format_timestamp(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// Lets clients measure their clock skew against the server before building
+/// any timestamped message.
+pub async fn server_time() -> impl Responder {
+    let now = Utc::now();
+    HttpResponse::Ok().json(TimeResponse {
+        unix: now.timestamp(),
+        rfc3339: format_timestamp(now),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn timestamps_are_utc_with_milliseconds() {
+        let time = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        assert_eq!(format_timestamp(time), "2024-05-01T12:00:00.000Z");
+
+        let time = time + chrono::Duration::microseconds(123_456);
+        assert_eq!(format_timestamp(time), "2024-05-01T12:00:00.123Z");
+    }
+}
@@ -0,0 +1,474 @@
+use std::env;
+use std::fmt;
+use std::fs;
+
+#[derive(Clone)]
+pub struct Config {
+    pub database_url: String,
+    pub host: String,
+    pub port: u16,
+    pub cors_origins: Vec<String>,
+    pub cors_max_age: usize,
+    pub cors_allow_credentials: bool,
+    pub db_max_connections: u32,
+    pub db_acquire_timeout_secs: u64,
+    pub db_statement_timeout_ms: u64,
+    pub log_sql: bool,
+    pub slow_query_ms: u64,
+    pub max_json_bytes: usize,
+    pub request_timeout_secs: u64,
+    pub workers: Option<usize>,
+    pub health_cache_max_age_secs: u32,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Missing(&'static str),
+    Invalid {
+        key: &'static str,
+        value: String,
+        reason: String,
+    },
+    Unreadable {
+        key: &'static str,
+        path: String,
+        reason: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Missing(key) => write!(f, "{} must be set", key),
+            ConfigError::Invalid { key, value, reason } => {
+                write!(f, "{} has invalid value {:?}: {}", key, value, reason)
+            }
+            ConfigError::Unreadable { key, path, reason } => {
+                write!(
+                    f,
+                    "{} points at {:?}, which could not be read: {}",
+                    key, path, reason
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Hand-written so `{:?}` never prints the database password.
+impl fmt::Debug for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Config")
+            .field("database_url", &"<redacted>")
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("cors_origins", &self.cors_origins)
+            .field("cors_max_age", &self.cors_max_age)
+            .field("cors_allow_credentials", &self.cors_allow_credentials)
+            .field("db_max_connections", &self.db_max_connections)
+            .field("db_acquire_timeout_secs", &self.db_acquire_timeout_secs)
+            .field("db_statement_timeout_ms", &self.db_statement_timeout_ms)
+            .field("log_sql", &self.log_sql)
+            .field("slow_query_ms", &self.slow_query_ms)
+            .field("max_json_bytes", &self.max_json_bytes)
+            .field("request_timeout_secs", &self.request_timeout_secs)
+            .field("workers", &self.workers)
+            .field("health_cache_max_age_secs", &self.health_cache_max_age_secs)
+            .finish()
+    }
+}
+
+impl Config {
+    /// Reads the configuration from the environment. Call once at startup,
+    /// after `dotenv()`, and share the result through `web::Data`.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Self::from_lookup(|key| env::var(key).ok())
+    }
+
+    /// Same as `from_env`, but reads variables through `lookup` so callers
+    /// (and tests) can supply them without touching the process environment.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, ConfigError> {
+        let lookup = &lookup;
+        let database_url = secret(lookup, "DATABASE_URL", "DATABASE_URL_FILE")?
+            .ok_or(ConfigError::Missing("DATABASE_URL"))?;
+        let host = optional(lookup, "HOST").unwrap_or_else(|| "127.0.0.1".to_string());
+        let port = parse_or(lookup, "PORT", 25000)?;
+        let cors_origins = optional(lookup, "CORS_ORIGINS")
+            .map(|origins| {
+                origins
+                    .split(',')
+                    .map(|o| o.trim().to_string())
+                    .filter(|o| !o.is_empty())
+                    .collect()
+            })
+            .unwrap_or_else(|| vec!["http://localhost:5173".to_string()]);
+        let cors_max_age = parse_or(lookup, "CORS_MAX_AGE", 3600)?;
+        let cors_allow_credentials = parse_or(lookup, "CORS_ALLOW_CREDENTIALS", false)?;
+        let db_max_connections = parse_or(lookup, "DB_MAX_CONNECTIONS", 5)?;
+        let db_acquire_timeout_secs = parse_or(lookup, "DB_ACQUIRE_TIMEOUT_SECS", 30)?;
+        let db_statement_timeout_ms = parse_or(lookup, "DB_STATEMENT_TIMEOUT_MS", 15_000)?;
+        let log_sql = parse_or(lookup, "LOG_SQL", false)?;
+        let slow_query_ms = parse_or(lookup, "SLOW_QUERY_MS", 1000)?;
+        let max_json_bytes = parse_or(lookup, "MAX_JSON_BYTES", 64 * 1024)?;
+        let request_timeout_secs = parse_or(lookup, "REQUEST_TIMEOUT_SECS", 30)?;
+        let workers = parse_optional(lookup, "WORKERS")?;
+        let health_cache_max_age_secs = parse_or(lookup, "HEALTH_CACHE_MAX_AGE_SECS", 5)?;
+
+        for origin in &cors_origins {
+            if let Err(reason) = check_origin(origin) {
+                return Err(ConfigError::Invalid {
+                    key: "CORS_ORIGINS",
+                    value: origin.clone(),
+                    reason: reason.to_string(),
+                });
+            }
+        }
+        if cors_allow_credentials && cors_origins.iter().any(|o| o == "*") {
+            return Err(ConfigError::Invalid {
+                key: "CORS_ORIGINS",
+                value: cors_origins.join(","),
+                reason:
+                    "cannot be \"*\" when CORS_ALLOW_CREDENTIALS is true; list explicit origins"
+                        .to_string(),
+            });
+        }
+        if db_max_connections == 0 {
+            return Err(ConfigError::Invalid {
+                key: "DB_MAX_CONNECTIONS",
+                value: "0".to_string(),
+                reason: "must be at least 1".to_string(),
+            });
+        }
+        if request_timeout_secs == 0 {
+            return Err(ConfigError::Invalid {
+                key: "REQUEST_TIMEOUT_SECS",
+                value: "0".to_string(),
+                reason: "must be at least 1".to_string(),
+            });
+        }
+        if workers == Some(0) {
+            return Err(ConfigError::Invalid {
+                key: "WORKERS",
+                value: "0".to_string(),
+                reason: "must be a positive integer".to_string(),
+            });
+        }
+
+        Ok(Config {
+            database_url,
+            host,
+            port,
+            cors_origins,
+            cors_max_age,
+            cors_allow_credentials,
+            db_max_connections,
+            db_acquire_timeout_secs,
+            db_statement_timeout_ms,
+            log_sql,
+            slow_query_ms,
+            max_json_bytes,
+            request_timeout_secs,
+            workers,
+            health_cache_max_age_secs,
+        })
+    }
+
+    pub fn bind_address(&self) -> (String, u16) {
+        (self.host.clone(), self.port)
+    }
+}
+
+/// A CORS origin is `*` or a scheme and host with an optional port, nothing else.
+fn check_origin(origin: &str) -> Result<(), &'static str> {
+    if origin == "*" {
+        return Ok(());
+    }
+    let rest = origin
+        .strip_prefix("http://")
+        .or_else(|| origin.strip_prefix("https://"))
+        .ok_or("must start with http:// or https://")?;
+    if rest.is_empty() {
+        return Err("has no host");
+    }
+    if rest.contains('/') {
+        return Err("must not contain a path or trailing slash");
+    }
+    if rest.contains(char::is_whitespace) {
+        return Err("must not contain whitespace");
+    }
+    Ok(())
+}
+
+type Lookup<'a> = &'a dyn Fn(&str) -> Option<String>;
+
+/// Returns the variable's value, treating unset and blank the same way.
+fn optional(lookup: Lookup, key: &str) -> Option<String> {
+    lookup(key)
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Resolves a secret from `<KEY>_FILE` if set, for deployments that mount
+/// secrets as files, and otherwise from `<KEY>` itself. The file wins when
+/// both are set. Trailing newlines in the file are ignored.
+fn secret(
+    lookup: Lookup,
+    key: &'static str,
+    file_key: &'static str,
+) -> Result<Option<String>, ConfigError> {
+    let Some(path) = optional(lookup, file_key) else {
+        return Ok(optional(lookup, key));
+    };
+    let contents = fs::read_to_string(&path).map_err(|e| ConfigError::Unreadable {
+        key: file_key,
+        path: path.clone(),
+        reason: e.to_string(),
+    })?;
+    let value = contents.trim_end_matches(['\r', '\n']);
+    if value.is_empty() {
+        return Err(ConfigError::Invalid {
+            key: file_key,
+            value: path,
+            reason: "file is empty".to_string(),
+        });
+    }
+    Ok(Some(value.to_string()))
+}
+
+fn parse_or<T>(lookup: Lookup, key: &'static str, default: T) -> Result<T, ConfigError>
+where
+    T: std::str::FromStr,
+    T::Err: fmt::Display,
+{
+    Ok(parse_optional(lookup, key)?.unwrap_or(default))
+}
+
+fn parse_optional<T>(lookup: Lookup, key: &'static str) -> Result<Option<T>, ConfigError>
+where
+    T: std::str::FromStr,
+    T::Err: fmt::Display,
+{
+    match optional(lookup, key) {
+        Some(value) => value
+            .parse()
+            .map(Some)
+            .map_err(|e: T::Err| ConfigError::Invalid {
+                key,
+                reason: e.to_string(),
+                value,
+            }),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn load(vars: &[(&str, &str)]) -> Result<Config, ConfigError> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        Config::from_lookup(|key| vars.get(key).cloned())
+    }
+
+    const DB: (&str, &str) = ("DATABASE_URL", "postgres://localhost/loki");
+
+    #[test]
+    fn missing_database_url_is_reported() {
+        let err = load(&[]).unwrap_err();
+        assert!(matches!(err, ConfigError::Missing("DATABASE_URL")));
+        assert_eq!(err.to_string(), "DATABASE_URL must be set");
+    }
+
+    #[test]
+    fn blank_database_url_counts_as_missing() {
+        let err = load(&[("DATABASE_URL", "   ")]).unwrap_err();
+        assert!(matches!(err, ConfigError::Missing("DATABASE_URL")));
+    }
+
+    #[test]
+    fn optional_values_fall_back_to_defaults() {
+        let config = load(&[DB]).unwrap();
+        assert_eq!(config.database_url, "postgres://localhost/loki");
+        assert_eq!(config.bind_address(), ("127.0.0.1".to_string(), 25000));
+        assert_eq!(config.cors_origins, vec!["http://localhost:5173"]);
+        assert_eq!(config.db_max_connections, 5);
+        assert_eq!(config.db_acquire_timeout_secs, 30);
+    }
+
+    #[test]
+    fn unparseable_port_names_the_variable() {
+        let err = load(&[DB, ("PORT", "http")]).unwrap_err();
+        match &err {
+            ConfigError::Invalid { key, value, .. } => {
+                assert_eq!(*key, "PORT");
+                assert_eq!(value, "http");
+            }
+            other => panic!("expected Invalid, got {:?}", other),
+        }
+        assert!(err
+            .to_string()
+            .starts_with("PORT has invalid value \"http\""));
+    }
+
+    #[test]
+    fn zero_max_connections_is_rejected() {
+        let err = load(&[DB, ("DB_MAX_CONNECTIONS", "0")]).unwrap_err();
+        assert!(matches!(
+            err,
+            ConfigError::Invalid {
+                key: "DB_MAX_CONNECTIONS",
+                ..
+            }
+        ));
+    }
+
+    /// Writes `contents` to a file unique to this test and returns its path.
+    fn secret_file(name: &str, contents: &str) -> String {
+        let path = env::temp_dir().join(format!("lokiai-config-{}-{}", std::process::id(), name));
+        fs::write(&path, contents).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn database_url_is_read_from_env() {
+        let config = load(&[DB]).unwrap();
+        assert_eq!(config.database_url, "postgres://localhost/loki");
+    }
+
+    #[test]
+    fn database_url_is_read_from_file_without_trailing_newline() {
+        let path = secret_file("from-file", "postgres://file/loki\n");
+        let config = load(&[("DATABASE_URL_FILE", &path)]).unwrap();
+        assert_eq!(config.database_url, "postgres://file/loki");
+    }
+
+    #[test]
+    fn database_url_file_wins_over_env() {
+        let path = secret_file("precedence", "postgres://file/loki");
+        let config = load(&[DB, ("DATABASE_URL_FILE", &path)]).unwrap();
+        assert_eq!(config.database_url, "postgres://file/loki");
+    }
+
+    #[test]
+    fn empty_database_url_file_is_reported_as_such() {
+        let path = secret_file("empty", "\n");
+        let err = load(&[DB, ("DATABASE_URL_FILE", &path)]).unwrap_err();
+        assert!(matches!(
+            err,
+            ConfigError::Invalid {
+                key: "DATABASE_URL_FILE",
+                ..
+            }
+        ));
+        assert!(err.to_string().ends_with("file is empty"), "{}", err);
+    }
+
+    #[test]
+    fn missing_database_url_file_is_unreadable() {
+        let err = load(&[("DATABASE_URL_FILE", "/nonexistent/lokiai-secret")]).unwrap_err();
+        assert!(matches!(
+            err,
+            ConfigError::Unreadable {
+                key: "DATABASE_URL_FILE",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn debug_output_redacts_database_url() {
+        let config = load(&[("DATABASE_URL", "postgres://loki:hunter2@db/loki")]).unwrap();
+        let debug = format!("{:?}", config);
+        assert!(!debug.contains("hunter2"), "{}", debug);
+        assert!(debug.contains("<redacted>"), "{}", debug);
+    }
+
+    #[test]
+    fn invalid_workers_value_is_rejected() {
+        for value in ["0", "-2", "four"] {
+            let err = load(&[DB, ("WORKERS", value)]).unwrap_err();
+            assert!(
+                matches!(err, ConfigError::Invalid { key: "WORKERS", .. }),
+                "WORKERS={}: {:?}",
+                value,
+                err
+            );
+            assert!(err.to_string().starts_with("WORKERS has invalid value"));
+        }
+        assert_eq!(load(&[DB, ("WORKERS", "3")]).unwrap().workers, Some(3));
+        assert_eq!(load(&[DB]).unwrap().workers, None);
+    }
+
+    #[test]
+    fn origin_format_is_checked() {
+        assert!(check_origin("*").is_ok());
+        assert!(check_origin("http://localhost:5173").is_ok());
+        assert!(check_origin("https://app.example.com").is_ok());
+        assert!(check_origin("app.example.com").is_err());
+        assert!(check_origin("https://").is_err());
+        assert!(check_origin("https://app.example.com/").is_err());
+        assert!(check_origin("https://app.example.com/path").is_err());
+        assert!(check_origin("https://app example.com").is_err());
+    }
+
+    #[test]
+    fn malformed_cors_origin_fails_config_loading() {
+        let err =
+            load(&[DB, ("CORS_ORIGINS", "https://ok.example,ftp://bad.example")]).unwrap_err();
+        match err {
+            ConfigError::Invalid { key, value, .. } => {
+                assert_eq!(key, "CORS_ORIGINS");
+                assert_eq!(value, "ftp://bad.example");
+            }
+            other => panic!("expected Invalid, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn cors_origins_are_split_and_trimmed() {
+        let config = load(&[
+            DB,
+            ("CORS_ORIGINS", "https://a.example, https://b.example,"),
+        ])
+        .unwrap();
+        assert_eq!(
+            config.cors_origins,
+            vec!["https://a.example", "https://b.example"]
+        );
+    }
+
+    #[test]
+    fn wildcard_origin_with_credentials_is_rejected() {
+        let err = load(&[
+            DB,
+            ("CORS_ORIGINS", "*"),
+            ("CORS_ALLOW_CREDENTIALS", "true"),
+        ])
+        .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                ConfigError::Invalid {
+                    key: "CORS_ORIGINS",
+                    ..
+                }
+            ),
+            "{:?}",
+            err
+        );
+        assert!(err.to_string().contains("CORS_ALLOW_CREDENTIALS"));
+
+        assert!(load(&[DB, ("CORS_ORIGINS", "*")]).is_ok());
+        assert!(load(&[
+            DB,
+            ("CORS_ORIGINS", "https://app.example"),
+            ("CORS_ALLOW_CREDENTIALS", "true"),
+        ])
+        .is_ok());
+    }
+}
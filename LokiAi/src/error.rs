@@ -0,0 +1,54 @@
+use actix_web::error::{InternalError, JsonPayloadError};
+use actix_web::http::header;
+use actix_web::{HttpRequest, HttpResponse};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+    pub message: String,
+}
+
+impl ErrorResponse {
+    pub fn new(error: &str, message: impl Into<String>) -> Self {
+        ErrorResponse {
+            error: error.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Replaces actix's plain-text JSON extractor errors with an `ErrorResponse`,
+/// answering oversized bodies with 413 rather than 400.
+pub fn json_error_handler(err: JsonPayloadError, _req: &HttpRequest) -> actix_web::Error {
+    let response = match &err {
+        JsonPayloadError::OverflowKnownLength { limit, .. }
+        | JsonPayloadError::Overflow { limit } => {
+            HttpResponse::PayloadTooLarge().json(ErrorResponse::new(
+                "payload_too_large",
+                format!("Request body exceeds the {} byte limit", limit),
+            ))
+        }
+        _ => HttpResponse::BadRequest().json(ErrorResponse::new("invalid_json", err.to_string())),
+    };
+    InternalError::from_response(err, response).into()
+}
+
+/// Fallback for unregistered paths, so clients always get a JSON body to parse.
+pub async fn not_found(req: HttpRequest) -> HttpResponse {
+    HttpResponse::NotFound().json(ErrorResponse::new(
+        "not_found",
+        format!("No route for {} {}", req.method(), req.path()),
+    ))
+}
+
+/// Response for a known path hit with an unsupported method. `allow` lists the
+/// methods the path does accept, for the `Allow` header.
+pub fn method_not_allowed(req: &HttpRequest, allow: &'static str) -> HttpResponse {
+    HttpResponse::MethodNotAllowed()
+        .insert_header((header::ALLOW, allow))
+        .json(ErrorResponse::new(
+            "method_not_allowed",
+            format!("{} is not supported on {}", req.method(), req.path()),
+        ))
+}
@@ -0,0 +1,195 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use actix_web::http::header::{CacheControl, CacheDirective};
+use actix_web::rt::time::timeout;
+use actix_web::{web, HttpResponse, Responder};
+use chrono::{TimeZone, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+
+use crate::clock::format_timestamp;
+use crate::config::Config;
+
+/// Upper bound on any single dependency probe, so /healthz answers promptly
+/// even when a dependency hangs.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Serialize)]
+struct HealthResponse {
+    status: &'static str,
+    timestamp: String,
+    version: &'static str,
+    git_sha: &'static str,
+    build_time: String,
+    pool: PoolStats,
+}
+
+#[derive(Serialize)]
+struct PoolStats {
+    size: u32,
+    idle: usize,
+    max_connections: u32,
+}
+
+impl PoolStats {
+    fn from_pool(pool: &PgPool) -> Self {
+        PoolStats {
+            size: pool.size(),
+            idle: pool.num_idle(),
+            max_connections: pool.options().get_max_connections(),
+        }
+    }
+}
+
+/// Build time stamped by build.rs, rendered in the same format as `timestamp`.
+fn build_time() -> String {
+    env!("BUILD_TIMESTAMP")
+        .parse::<i64>()
+        .ok()
+        .and_then(|secs| Utc.timestamp_opt(secs, 0).single())
+        .map(format_timestamp)
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Lets a polling client reuse a `/health` answer for a few seconds. It is
+/// `private` because the body carries pool stats that shared caches should
+/// not hold. A max age of 0 turns caching off entirely.
+fn cache_control(config: &Config) -> CacheControl {
+    match config.health_cache_max_age_secs {
+        0 => CacheControl(vec![CacheDirective::NoStore]),
+        max_age => CacheControl(vec![
+            CacheDirective::Private,
+            CacheDirective::MaxAge(max_age),
+        ]),
+    }
+}
+
+pub async fn health(config: web::Data<Config>, pool: web::Data<PgPool>) -> impl Responder {
+    HttpResponse::Ok()
+        .insert_header(cache_control(&config))
+        .json(HealthResponse {
+            status: "ok",
+            timestamp: format_timestamp(Utc::now()),
+            version: env!("CARGO_PKG_VERSION"),
+            git_sha: env!("GIT_SHA"),
+            build_time: build_time(),
+            pool: PoolStats::from_pool(&pool),
+        })
+}
+
+#[derive(Serialize)]
+struct HealthzResponse {
+    status: &'static str,
+    checks: BTreeMap<&'static str, Check>,
+}
+
+#[derive(Serialize)]
+struct Check {
+    status: &'static str,
+    required: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl Check {
+    fn from_result(required: bool, result: Result<(), String>) -> Self {
+        match result {
+            Ok(()) => Check {
+                status: "ok",
+                required,
+                error: None,
+            },
+            Err(e) => Check {
+                status: "down",
+                required,
+                error: Some(e),
+            },
+        }
+    }
+}
+
+async fn check_database(pool: &PgPool) -> Result<(), String> {
+    match timeout(CHECK_TIMEOUT, sqlx::query("SELECT 1").execute(pool)).await {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err(format!("no response within {}s", CHECK_TIMEOUT.as_secs())),
+    }
+}
+
+fn rollup(checks: &BTreeMap<&'static str, Check>) -> &'static str {
+    let failed = |required: bool| {
+        checks
+            .values()
+            .any(|check| check.required == required && check.status != "ok")
+    };
+    if failed(true) {
+        "unhealthy"
+    } else if failed(false) {
+        "degraded"
+    } else {
+        "ok"
+    }
+}
+
+/// Rolls every dependency probe into one status: `unhealthy` (503) when a
+/// required check fails, `degraded` (200) when only optional ones do.
+/// Never cached: a stored `ok` or `unhealthy` answer would defeat the probe.
+pub async fn healthz(pool: web::Data<PgPool>) -> impl Responder {
+    let mut checks = BTreeMap::new();
+    checks.insert(
+        "database",
+        Check::from_result(true, check_database(&pool).await),
+    );
+
+    let status = rollup(&checks);
+    let mut response = if status == "unhealthy" {
+        HttpResponse::ServiceUnavailable()
+    } else {
+        HttpResponse::Ok()
+    };
+    response
+        .insert_header(CacheControl(vec![CacheDirective::NoStore]))
+        .json(HealthzResponse { status, checks })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checks(results: &[(&'static str, bool, bool)]) -> BTreeMap<&'static str, Check> {
+        results
+            .iter()
+            .map(|&(name, required, ok)| {
+                let result = if ok { Ok(()) } else { Err("down".to_string()) };
+                (name, Check::from_result(required, result))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn all_checks_passing_is_ok() {
+        assert_eq!(rollup(&checks(&[("database", true, true)])), "ok");
+        assert_eq!(
+            rollup(&checks(&[("database", true, true), ("rpc", false, true)])),
+            "ok"
+        );
+    }
+
+    #[test]
+    fn failing_optional_check_is_degraded() {
+        assert_eq!(
+            rollup(&checks(&[("database", true, true), ("rpc", false, false)])),
+            "degraded"
+        );
+    }
+
+    #[test]
+    fn failing_required_check_is_unhealthy() {
+        assert_eq!(rollup(&checks(&[("database", true, false)])), "unhealthy");
+        assert_eq!(
+            rollup(&checks(&[("database", true, false), ("rpc", false, false)])),
+            "unhealthy"
+        );
+    }
+}
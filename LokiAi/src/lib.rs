@@ -0,0 +1,108 @@
+pub mod access_log;
+pub mod clock;
+pub mod config;
+pub mod error;
+pub mod health;
+pub mod routes;
+pub mod selfcheck;
+pub mod timeout;
+
+use actix_cors::Cors;
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceFactory, ServiceRequest, ServiceResponse};
+use actix_web::{middleware, web, App};
+use log::LevelFilter;
+use sqlx::postgres::PgConnectOptions;
+use sqlx::{ConnectOptions, PgPool};
+use std::time::Duration;
+
+use crate::access_log::access_log;
+use crate::config::Config;
+use crate::error::{json_error_handler, not_found};
+use crate::timeout::request_timeout;
+
+/// Builds the CORS layer from config. A `*` entry allows any origin; config
+/// loading already rejects that combined with credentials, so a credentialed
+/// response always names an explicit origin.
+pub fn cors(config: &Config) -> Cors {
+    let cors = if config.cors_origins.iter().any(|o| o == "*") {
+        Cors::default().allow_any_origin().send_wildcard()
+    } else {
+        config
+            .cors_origins
+            .iter()
+            .fold(Cors::default(), |cors, origin| cors.allowed_origin(origin))
+    };
+    let cors = cors
+        .allow_any_method()
+        .allow_any_header()
+        .max_age(config.cors_max_age);
+
+    if config.cors_allow_credentials {
+        cors.supports_credentials()
+    } else {
+        cors
+    }
+}
+
+pub fn connect_options(config: &Config) -> Result<PgConnectOptions, sqlx::Error> {
+    let statement_level = if config.log_sql {
+        LevelFilter::Info
+    } else {
+        LevelFilter::Off
+    };
+
+    // Postgres aborts any statement running past the timeout, so a runaway
+    // query fails cleanly instead of holding a connection and a worker.
+    Ok(config
+        .database_url
+        .parse::<PgConnectOptions>()?
+        .options([(
+            "statement_timeout",
+            config.db_statement_timeout_ms.to_string(),
+        )])
+        .log_statements(statement_level)
+        .log_slow_statements(
+            LevelFilter::Warn,
+            Duration::from_millis(config.slow_query_ms),
+        ))
+}
+
+/// JSON extractor settings shared by every route: the configured body limit,
+/// with failures rendered as `ErrorResponse`.
+pub fn json_config(config: &Config) -> web::JsonConfig {
+    web::JsonConfig::default()
+        .limit(config.max_json_bytes)
+        .error_handler(json_error_handler)
+}
+
+/// The full application: middleware, shared state and every route. Used by
+/// `main` for each worker and by the integration tests.
+pub fn app(
+    config: web::Data<Config>,
+    pool: web::Data<PgPool>,
+) -> App<
+    impl ServiceFactory<
+        ServiceRequest,
+        Config = (),
+        Response = ServiceResponse<impl MessageBody>,
+        Error = actix_web::Error,
+        InitError = (),
+    >,
+> {
+    let request_limit = Duration::from_secs(config.request_timeout_secs);
+
+    App::new()
+        .wrap(middleware::from_fn(move |req, next| {
+            request_timeout(request_limit, req, next)
+        }))
+        .wrap(middleware::Compress::default())
+        .wrap(cors(&config))
+        .wrap(middleware::from_fn(access_log))
+        .app_data(json_config(&config))
+        .app_data(config)
+        .app_data(pool)
+        .service(web::scope(routes::V1).configure(routes::configure))
+        .configure(routes::configure)
+        .default_service(web::route().to(not_found))
+}
@@ -1,31 +1,67 @@
-use actix_web::{get, App, HttpServer, Responder};
-use sqlx::postgres::PgPoolOptions;
+use actix_web::{web, HttpServer};
 use dotenv::dotenv;
-use std::env;
+use sqlx::postgres::PgPoolOptions;
+use std::time::Duration;
+use tracing::{error, info};
 
-#[get("/")]
-async fn hello() -> impl Responder {
-    "Hello from Cross-Chain AI Backend!"
-}
+use lokiai_backend::config::Config;
+use lokiai_backend::{app, connect_options, selfcheck};
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     dotenv().ok();
-    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set in .env");
+    tracing_subscriber::fmt::init();
+
+    let config = match Config::from_env() {
+        Ok(config) => config,
+        Err(e) => {
+            error!("Invalid configuration: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let connect_options = match connect_options(&config) {
+        Ok(options) => options,
+        Err(e) => {
+            error!("Invalid configuration: DATABASE_URL: {}", e);
+            std::process::exit(1);
+        }
+    };
+
     let pool = PgPoolOptions::new()
-        .max_connections(5)
-        .connect(&database_url)
-        .await
-        .expect("Failed to connect to database");
+        .max_connections(config.db_max_connections)
+        .acquire_timeout(Duration::from_secs(config.db_acquire_timeout_secs))
+        .connect_lazy_with(connect_options);
+
+    let failures = selfcheck::run(&pool).await;
+    if !failures.is_empty() {
+        for failure in &failures {
+            error!("Startup check failed: {}", failure);
+        }
+        std::process::exit(selfcheck::EXIT_CODE);
+    }
 
-    println!("Connected to the database!");
-    println!("Starting server at http://.bind("127.0.0.1:25000")?");
+    info!("Connected to the database!");
+    info!("Starting server at http://{}:{}", config.host, config.port);
 
-    HttpServer::new(|| App::new().service(hello))
-        .bind("127.0.0.1:25000")?
+    let bind_address = config.bind_address();
+    let workers = config.workers;
+    let config = web::Data::new(config);
+    let pool = web::Data::new(pool);
 
-  // Check if bind returns error
+    // Same fallback actix uses, resolved here so the effective count is logged.
+    let (workers, source) = match workers {
+        Some(workers) => (workers, "WORKERS"),
+        None => (
+            std::thread::available_parallelism().map_or(1, |n| n.get()),
+            "available CPU parallelism",
+        ),
+    };
+    info!("Using {} workers (from {})", workers, source);
+
+    HttpServer::new(move || app(config.clone(), pool.clone()))
+        .workers(workers)
+        .bind(bind_address)?
         .run()
         .await
 }
-
@@ -0,0 +1,44 @@
+use actix_web::{guard, web, FromRequest, Handler, HttpRequest, Resource, Responder};
+
+use crate::clock;
+use crate::error::method_not_allowed;
+use crate::health;
+
+/// Prefix for the current API version. Routes are also mounted unprefixed
+/// while clients migrate.
+pub const V1: &str = "/v1";
+
+async fn hello() -> impl Responder {
+    "Hello from Cross-Chain AI Backend!"
+}
+
+/// Registers a read-only route answering GET and HEAD. Other methods get a
+/// JSON 405 with an `Allow` header instead of falling through to the 404
+/// default service.
+fn get_only<F, Args>(path: &str, handler: F) -> Resource
+where
+    F: Handler<Args>,
+    Args: FromRequest + 'static,
+    F::Output: Responder + 'static,
+{
+    web::resource(path)
+        .route(
+            web::route()
+                .guard(guard::Any(guard::Get()).or(guard::Head()))
+                .to(handler),
+        )
+        .default_service(web::to(|req: HttpRequest| async move {
+            method_not_allowed(&req, "GET, HEAD")
+        }))
+}
+
+/// Every route the server exposes, for mounting under a version scope.
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    // "" is the bare scope path, so `/v1` answers like `/v1/` and `/`. At the
+    // app root it never matches, since request paths always start with `/`.
+    cfg.service(get_only("", hello))
+        .service(get_only("/", hello))
+        .service(get_only("/health", health::health))
+        .service(get_only("/healthz", health::healthz))
+        .service(get_only("/time", clock::server_time));
+}
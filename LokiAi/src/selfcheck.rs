@@ -0,0 +1,68 @@
+use std::fmt;
+use std::time::Duration;
+
+use actix_web::rt::time::timeout;
+use sqlx::PgPool;
+
+/// Exit code used when a startup dependency check fails. Configuration errors,
+/// including malformed CORS origins, exit with 1 from config loading, so
+/// scripts can tell "fix the env" apart from "a dependency is down".
+pub const EXIT_CODE: i32 = 2;
+
+/// How long startup waits for the database before giving up, well under the
+/// pool's acquire timeout so an unreachable host fails fast.
+const DATABASE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug)]
+pub struct CheckFailure {
+    pub check: &'static str,
+    pub detail: String,
+}
+
+impl fmt::Display for CheckFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.check, self.detail)
+    }
+}
+
+/// Runs every startup dependency check and returns all failures, so a broken
+/// deployment reports everything wrong at once instead of one problem per boot.
+pub async fn run(pool: &PgPool) -> Vec<CheckFailure> {
+    let mut failures = Vec::new();
+
+    let detail = match timeout(DATABASE_TIMEOUT, sqlx::query("SELECT 1").execute(pool)).await {
+        Ok(Ok(_)) => None,
+        Ok(Err(e)) => Some(format!("not reachable: {}", e)),
+        Err(_) => Some(format!(
+            "no response within {}s",
+            DATABASE_TIMEOUT.as_secs()
+        )),
+    };
+    if let Some(detail) = detail {
+        failures.push(CheckFailure {
+            check: "database",
+            detail,
+        });
+    }
+
+    failures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::postgres::PgPoolOptions;
+
+    #[actix_web::test]
+    async fn unreachable_database_is_reported() {
+        let pool = PgPoolOptions::new()
+            .acquire_timeout(Duration::from_secs(1))
+            .connect_lazy("postgres://postgres@127.0.0.1:1/loki")
+            .unwrap();
+
+        let failures = run(&pool).await;
+
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].check, "database");
+    }
+}
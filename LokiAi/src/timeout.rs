@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::error::InternalError;
+use actix_web::middleware::Next;
+use actix_web::rt::time::timeout;
+use actix_web::{Error, HttpResponse};
+
+use crate::error::ErrorResponse;
+
+/// Aborts any request that runs longer than `limit` with a 504, so a hung
+/// database call cannot hold a worker indefinitely. Wrap it with
+/// `middleware::from_fn` and the configured `REQUEST_TIMEOUT_SECS`.
+pub async fn request_timeout(
+    limit: Duration,
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    match timeout(limit, next.call(req)).await {
+        Ok(res) => res,
+        Err(_) => {
+            let response = HttpResponse::GatewayTimeout().json(ErrorResponse::new(
+                "request_timeout",
+                format!("Request did not complete within {} ms", limit.as_millis()),
+            ));
+            Err(InternalError::from_response("request timed out", response).into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::body::to_bytes;
+    use actix_web::http::StatusCode;
+    use actix_web::{middleware, test, web, App};
+
+    async fn slow() -> &'static str {
+        actix_web::rt::time::sleep(Duration::from_millis(500)).await;
+        "done"
+    }
+
+    async fn fast() -> &'static str {
+        "done"
+    }
+
+    #[actix_web::test]
+    async fn slow_handler_gets_504_error_response() {
+        let limit = Duration::from_millis(50);
+        let app = test::init_service(
+            App::new()
+                .wrap(middleware::from_fn(move |req, next| {
+                    request_timeout(limit, req, next)
+                }))
+                .route("/slow", web::get().to(slow))
+                .route("/fast", web::get().to(fast)),
+        )
+        .await;
+
+        // The 504 travels as an error so outer middleware (CORS, access log)
+        // still decorate it; the server renders it exactly like this.
+        let err = test::try_call_service(&app, test::TestRequest::get().uri("/slow").to_request())
+            .await
+            .err()
+            .expect("slow request times out");
+        let res = err.error_response();
+        assert_eq!(res.status(), StatusCode::GATEWAY_TIMEOUT);
+        let body = to_bytes(res.into_body()).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"], "request_timeout");
+
+        let res =
+            test::call_service(&app, test::TestRequest::get().uri("/fast").to_request()).await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+}
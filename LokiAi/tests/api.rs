@@ -0,0 +1,282 @@
+mod common;
+
+use actix_web::http::{header, StatusCode};
+use actix_web::{test, web, App, HttpResponse};
+use serde_json::Value;
+
+#[actix_web::test]
+async fn root_returns_greeting() {
+    let app = common::init_default().await;
+    let res = test::call_service(&app, test::TestRequest::get().uri("/").to_request()).await;
+
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = test::read_body(res).await;
+    assert_eq!(body, "Hello from Cross-Chain AI Backend!");
+}
+
+#[actix_web::test]
+async fn health_reports_ok() {
+    let app = common::init_default().await;
+    let req = test::TestRequest::get().uri("/health").to_request();
+    let body: Value = test::call_and_read_body_json(&app, req).await;
+
+    assert_eq!(body["status"], "ok");
+}
+
+#[actix_web::test]
+async fn health_reports_build_info() {
+    let app = common::init_default().await;
+    let req = test::TestRequest::get().uri("/health").to_request();
+    let body: Value = test::call_and_read_body_json(&app, req).await;
+
+    assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+    assert!(!body["git_sha"].as_str().unwrap().is_empty());
+    assert!(body["build_time"].as_str().unwrap().ends_with('Z'));
+}
+
+fn cache_control<B>(res: &actix_web::dev::ServiceResponse<B>) -> &str {
+    res.headers()
+        .get(header::CACHE_CONTROL)
+        .expect("Cache-Control is set")
+        .to_str()
+        .unwrap()
+}
+
+#[actix_web::test]
+async fn health_is_privately_cacheable() {
+    let app = common::init_default().await;
+    let res = test::call_service(&app, test::TestRequest::get().uri("/health").to_request()).await;
+
+    assert_eq!(cache_control(&res), "private, max-age=5");
+}
+
+#[actix_web::test]
+async fn health_cache_can_be_disabled() {
+    let config = common::config(&[("HEALTH_CACHE_MAX_AGE_SECS", "0")]);
+    let app = common::init(config, common::lazy_pool(common::UNREACHABLE_DATABASE_URL)).await;
+    let res = test::call_service(&app, test::TestRequest::get().uri("/health").to_request()).await;
+
+    assert_eq!(cache_control(&res), "no-store");
+}
+
+#[actix_web::test]
+async fn healthz_is_never_cached() {
+    let app = common::init_default().await;
+    let res = test::call_service(&app, test::TestRequest::get().uri("/healthz").to_request()).await;
+
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(cache_control(&res), "no-store");
+}
+
+#[actix_web::test]
+async fn unsupported_method_gets_json_405() {
+    let app = common::init_default().await;
+    let res = test::call_service(&app, test::TestRequest::post().uri("/health").to_request()).await;
+
+    assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(res.headers().get(header::ALLOW).unwrap(), "GET, HEAD");
+    let body: Value = test::read_body_json(res).await;
+    assert_eq!(body["error"], "method_not_allowed");
+    assert_eq!(body["message"], "POST is not supported on /health");
+}
+
+#[actix_web::test]
+async fn head_is_served_like_get() {
+    let app = common::init_default().await;
+    for path in ["/", "/health"] {
+        let req = test::TestRequest::default()
+            .method(actix_web::http::Method::HEAD)
+            .uri(path)
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::OK, "HEAD {}", path);
+    }
+}
+
+#[actix_web::test]
+async fn oversized_json_body_gets_413() {
+    // No route accepts a body yet, so exercise the shared extractor config directly.
+    let config = common::config(&[("MAX_JSON_BYTES", "16")]);
+    let app = test::init_service(
+        App::new()
+            .app_data(lokiai_backend::json_config(&config))
+            .route(
+                "/echo",
+                web::post().to(|body: web::Json<Value>| async move {
+                    HttpResponse::Ok().json(body.into_inner())
+                }),
+            ),
+    )
+    .await;
+
+    let small = test::TestRequest::post()
+        .uri("/echo")
+        .set_json(serde_json::json!({ "a": 1 }))
+        .to_request();
+    assert_eq!(
+        test::call_service(&app, small).await.status(),
+        StatusCode::OK
+    );
+
+    let large = test::TestRequest::post()
+        .uri("/echo")
+        .set_json(serde_json::json!({ "payload": "x".repeat(64) }))
+        .to_request();
+    let res = test::call_service(&app, large).await;
+    assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    let body: Value = test::read_body_json(res).await;
+    assert_eq!(body["error"], "payload_too_large");
+}
+
+#[actix_web::test]
+async fn unknown_route_gets_json_404() {
+    let app = common::init_default().await;
+    let res = test::call_service(&app, test::TestRequest::get().uri("/nope").to_request()).await;
+
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    let body: Value = test::read_body_json(res).await;
+    assert_eq!(body["error"], "not_found");
+    assert_eq!(body["message"], "No route for GET /nope");
+}
+
+#[actix_web::test]
+async fn time_matches_the_server_clock() {
+    let app = common::init_default().await;
+    let req = test::TestRequest::get().uri("/time").to_request();
+    let body: Value = test::call_and_read_body_json(&app, req).await;
+
+    let unix = body["unix"].as_i64().expect("unix is an integer");
+    assert!((unix - chrono::Utc::now().timestamp()).abs() <= 1);
+
+    let rfc3339 = body["rfc3339"].as_str().unwrap();
+    let parsed = chrono::DateTime::parse_from_rfc3339(rfc3339).expect("rfc3339 parses");
+    assert_eq!(parsed.timestamp(), unix);
+}
+
+#[actix_web::test]
+async fn healthz_reports_down_database_as_unhealthy() {
+    let app = common::init_default().await;
+    let res = test::call_service(&app, test::TestRequest::get().uri("/healthz").to_request()).await;
+
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let body: Value = test::read_body_json(res).await;
+    assert_eq!(body["status"], "unhealthy");
+    assert_eq!(body["checks"]["database"]["status"], "down");
+    assert_eq!(body["checks"]["database"]["required"], true);
+    assert!(body["checks"]["database"]["error"].is_string());
+}
+
+#[actix_web::test]
+#[ignore = "needs TEST_DATABASE_URL"]
+async fn healthz_reports_reachable_database_as_ok() {
+    let url = common::test_database_url();
+    let app = common::init(common::config(&[]), common::lazy_pool(&url)).await;
+    let res = test::call_service(&app, test::TestRequest::get().uri("/healthz").to_request()).await;
+
+    assert_eq!(res.status(), StatusCode::OK);
+    let body: Value = test::read_body_json(res).await;
+    assert_eq!(body["status"], "ok");
+    assert_eq!(body["checks"]["database"]["status"], "ok");
+    assert!(body["checks"]["database"].get("error").is_none());
+}
+
+#[actix_web::test]
+async fn routes_are_served_with_and_without_version_prefix() {
+    let app = common::init_default().await;
+    for path in [
+        "/",
+        "/v1",
+        "/v1/",
+        "/health",
+        "/v1/health",
+        "/time",
+        "/v1/time",
+    ] {
+        let res = test::call_service(&app, test::TestRequest::get().uri(path).to_request()).await;
+        assert_eq!(res.status(), StatusCode::OK, "GET {}", path);
+    }
+}
+
+#[actix_web::test]
+async fn health_reports_pool_stats() {
+    let config = common::config(&[("DB_MAX_CONNECTIONS", "7")]);
+    let pool = sqlx::postgres::PgPoolOptions::new()
+        .max_connections(config.db_max_connections)
+        .connect_lazy(common::UNREACHABLE_DATABASE_URL)
+        .unwrap();
+    let app = common::init(config, pool).await;
+    let req = test::TestRequest::get().uri("/health").to_request();
+    let body: Value = test::call_and_read_body_json(&app, req).await;
+
+    let pool = &body["pool"];
+    assert!(pool["size"].is_u64(), "{}", pool);
+    assert!(pool["idle"].is_u64(), "{}", pool);
+    assert_eq!(pool["max_connections"], 7);
+}
+
+#[actix_web::test]
+async fn responses_are_compressed_when_accepted() {
+    let app = common::init_default().await;
+
+    let req = test::TestRequest::get()
+        .uri("/health")
+        .insert_header((header::ACCEPT_ENCODING, "gzip"))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers().get(header::CONTENT_ENCODING).unwrap(), "gzip");
+
+    let res = test::call_service(&app, test::TestRequest::get().uri("/health").to_request()).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(res.headers().get(header::CONTENT_ENCODING).is_none());
+}
+
+fn preflight(origin: &str) -> actix_http::Request {
+    test::TestRequest::default()
+        .method(actix_web::http::Method::OPTIONS)
+        .uri("/health")
+        .insert_header((header::ORIGIN, origin))
+        .insert_header((header::ACCESS_CONTROL_REQUEST_METHOD, "GET"))
+        .to_request()
+}
+
+#[actix_web::test]
+async fn cors_preflight_with_credentials_echoes_the_origin() {
+    let config = common::config(&[
+        ("CORS_ORIGINS", "https://app.example"),
+        ("CORS_ALLOW_CREDENTIALS", "true"),
+    ]);
+    let app = common::init(config, common::lazy_pool(common::UNREACHABLE_DATABASE_URL)).await;
+    let res = test::call_service(&app, preflight("https://app.example")).await;
+
+    assert_eq!(res.status(), StatusCode::OK);
+    let headers = res.headers();
+    assert_eq!(
+        headers.get(header::ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+        "https://app.example"
+    );
+    assert_eq!(
+        headers
+            .get(header::ACCESS_CONTROL_ALLOW_CREDENTIALS)
+            .unwrap(),
+        "true"
+    );
+}
+
+#[actix_web::test]
+async fn cors_preflight_with_wildcard_sends_star() {
+    let config = common::config(&[("CORS_ORIGINS", "*"), ("CORS_MAX_AGE", "600")]);
+    let app = common::init(config, common::lazy_pool(common::UNREACHABLE_DATABASE_URL)).await;
+    let res = test::call_service(&app, preflight("https://anywhere.example")).await;
+
+    assert_eq!(res.status(), StatusCode::OK);
+    let headers = res.headers();
+    assert_eq!(
+        headers.get(header::ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+        "*"
+    );
+    assert_eq!(headers.get(header::ACCESS_CONTROL_MAX_AGE).unwrap(), "600");
+    assert!(headers
+        .get(header::ACCESS_CONTROL_ALLOW_CREDENTIALS)
+        .is_none());
+}
@@ -0,0 +1,113 @@
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{Service, ServiceResponse};
+use actix_web::{test, web};
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use tracing::subscriber::DefaultGuard;
+
+use lokiai_backend::app;
+use lokiai_backend::config::Config;
+
+/// Points at a port nothing listens on, so any query fails fast.
+pub const UNREACHABLE_DATABASE_URL: &str = "postgres://postgres@127.0.0.1:1/loki";
+
+/// Builds a `Config` from the given overrides on top of a placeholder
+/// `DATABASE_URL`, without reading the process environment.
+pub fn config(vars: &[(&str, &str)]) -> Config {
+    let mut all: HashMap<String, String> = HashMap::new();
+    all.insert(
+        "DATABASE_URL".to_string(),
+        UNREACHABLE_DATABASE_URL.to_string(),
+    );
+    for (key, value) in vars {
+        all.insert(key.to_string(), value.to_string());
+    }
+    Config::from_lookup(|key| all.get(key).cloned()).expect("test config is valid")
+}
+
+/// A pool that never connects until used. Routes that don't query the
+/// database can be tested against it without a running Postgres.
+pub fn lazy_pool(url: &str) -> PgPool {
+    PgPoolOptions::new()
+        .acquire_timeout(Duration::from_secs(1))
+        .connect_lazy(url)
+        .expect("database url parses")
+}
+
+/// Postgres to run database-backed tests against, from `TEST_DATABASE_URL`.
+/// Those tests are `#[ignore]`d so a plain `cargo test` reports them as
+/// skipped; run them with `TEST_DATABASE_URL=... cargo test -- --ignored`.
+pub fn test_database_url() -> String {
+    std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL must be set for database tests")
+}
+
+/// The full application as `main` builds it, ready for `test::call_service`.
+pub async fn init(
+    config: Config,
+    pool: PgPool,
+) -> impl Service<
+    actix_http::Request,
+    Response = ServiceResponse<impl MessageBody>,
+    Error = actix_web::Error,
+> {
+    test::init_service(app(web::Data::new(config), web::Data::new(pool))).await
+}
+
+/// The default application with an unreachable database behind it.
+pub async fn init_default() -> impl Service<
+    actix_http::Request,
+    Response = ServiceResponse<impl MessageBody>,
+    Error = actix_web::Error,
+> {
+    init(config(&[]), lazy_pool(UNREACHABLE_DATABASE_URL)).await
+}
+
+#[derive(Clone, Default)]
+struct Captured(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Tracing output recorded on the current thread while this is alive.
+pub struct LogCapture {
+    captured: Captured,
+    _guard: DefaultGuard,
+}
+
+impl LogCapture {
+    /// Everything logged so far, one event per line, without ANSI colours.
+    pub fn output(&self) -> String {
+        String::from_utf8(self.captured.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+/// Routes tracing events on this thread into memory until the returned
+/// capture is dropped. `actix_web::test` runs on one thread, so everything
+/// the test awaits is recorded.
+pub fn capture_logs() -> LogCapture {
+    let captured = Captured::default();
+    let writer = captured.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(move || writer.clone())
+        .with_ansi(false)
+        .finish();
+    LogCapture {
+        captured,
+        _guard: tracing::subscriber::set_default(subscriber),
+    }
+}
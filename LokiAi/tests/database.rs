@@ -0,0 +1,58 @@
+mod common;
+
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+
+use lokiai_backend::connect_options;
+
+/// A pool built the way `main` builds it, against `TEST_DATABASE_URL`.
+async fn pool(url: &str, vars: &[(&str, &str)]) -> PgPool {
+    let mut vars = vars.to_vec();
+    vars.push(("DATABASE_URL", url));
+    let config = common::config(&vars);
+    PgPoolOptions::new()
+        .max_connections(1)
+        .connect_with(connect_options(&config).expect("database url parses"))
+        .await
+        .expect("test database is reachable")
+}
+
+#[actix_web::test]
+#[ignore = "needs TEST_DATABASE_URL"]
+async fn statement_timeout_aborts_long_queries() {
+    let url = common::test_database_url();
+    let pool = pool(&url, &[("DB_STATEMENT_TIMEOUT_MS", "100")]).await;
+
+    let err = sqlx::query("SELECT pg_sleep(1)")
+        .execute(&pool)
+        .await
+        .expect_err("query outlives the statement timeout");
+    let code = err.as_database_error().and_then(|e| e.code());
+    assert_eq!(code.as_deref(), Some("57014"), "{}", err);
+
+    sqlx::query("SELECT pg_sleep(0.01)")
+        .execute(&pool)
+        .await
+        .expect("short query completes");
+}
+
+#[actix_web::test]
+#[ignore = "needs TEST_DATABASE_URL"]
+async fn slow_queries_are_logged() {
+    let url = common::test_database_url();
+    let logs = common::capture_logs();
+
+    let pool = pool(&url, &[("SLOW_QUERY_MS", "10")]).await;
+    sqlx::query("SELECT pg_sleep(0.1)")
+        .execute(&pool)
+        .await
+        .expect("query completes");
+
+    let output = logs.output();
+    let slow = output
+        .lines()
+        .find(|line| line.contains("slow statement"))
+        .unwrap_or_else(|| panic!("no slow-statement warning in:\n{}", output));
+    assert!(slow.contains("WARN"), "{}", slow);
+    assert!(slow.contains("pg_sleep(0.1)"), "{}", slow);
+}